@@ -1,7 +1,31 @@
-use std::{ffi::OsString, os::windows::ffi::OsStringExt, time::Duration};
+use std::{
+    ffi::OsString,
+    os::windows::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, OnceLock,
+    },
+    time::Duration,
+};
 
 use clap::Parser;
+use serde::Deserialize;
 use thiserror::Error;
+use winapi::{
+    shared::{
+        minwindef::{LPARAM, LRESULT, UINT, WPARAM},
+        windef::{HHOOK, HWND, RECT},
+    },
+    um::{
+        shellapi::{Shell_NotifyIconW, NIF_ICON, NIF_MESSAGE, NIF_TIP, NIM_ADD, NIM_DELETE, NOTIFYICONDATAW},
+        winuser::{
+            KBDLLHOOKSTRUCT, MSLLHOOKSTRUCT, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN, WM_LBUTTONUP,
+            WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_RBUTTONDOWN, WM_RBUTTONUP,
+            WM_SYSKEYDOWN, WM_SYSKEYUP, WM_XBUTTONDOWN, WM_XBUTTONUP,
+        },
+    },
+};
 
 const RIGHT_MOUSE_BUTTON: i32 = winapi::um::winuser::VK_RBUTTON;
 const LEFT_MOUSE_BUTTON: i32 = winapi::um::winuser::VK_LBUTTON;
@@ -13,6 +37,8 @@ enum Error {
     GetMousePosition,
     #[error("Failed to set mouse position")]
     SetMousePosition,
+    #[error("Failed to clip cursor")]
+    ClipCursor,
 }
 
 type Result<T> = std::result::Result<T, Error>;
@@ -32,8 +58,67 @@ unsafe fn set_mouse_position(x: i32, y: i32) -> Result<()> {
     Ok(())
 }
 
-unsafe fn get_mouse_button_pressed(button: i32) -> bool {
-    winapi::um::winuser::GetAsyncKeyState(button) != 0
+unsafe fn is_trigger_held(vk: i32) -> bool {
+    winapi::um::winuser::GetAsyncKeyState(vk) != 0
+}
+
+/// Parses a single `--trigger` value into a virtual-key code: either a raw
+/// numeric VK code, or a symbolic name for a mouse button or keyboard key.
+/// Returns `None` for anything unrecognized.
+///
+/// `1`/`2`/`3` are special-cased to the old `--button` flag's left/right/
+/// middle mouse enum rather than treated as raw VK codes, since `VK_MBUTTON`
+/// is actually `4` — a bare `--trigger 3` migrated from `--button 3` would
+/// otherwise silently resolve to `VK_CANCEL` and never engage.
+fn parse_trigger(input: &str) -> Option<i32> {
+    match input {
+        "1" => return Some(LEFT_MOUSE_BUTTON),
+        "2" => return Some(RIGHT_MOUSE_BUTTON),
+        "3" => return Some(MIDDLE_MOUSE_BUTTON),
+        _ => {}
+    }
+    if let Ok(code) = input.parse::<i32>() {
+        return Some(code);
+    }
+
+    use winapi::um::winuser::*;
+    let lower = input.to_ascii_lowercase();
+    Some(match lower.as_str() {
+        "mouse1" | "lmb" => LEFT_MOUSE_BUTTON,
+        "mouse2" | "rmb" => RIGHT_MOUSE_BUTTON,
+        "mouse3" | "mmb" => MIDDLE_MOUSE_BUTTON,
+        "mouse4" => VK_XBUTTON1,
+        "mouse5" => VK_XBUTTON2,
+        "lalt" => VK_LMENU,
+        "ralt" => VK_RMENU,
+        "lctrl" | "lcontrol" => VK_LCONTROL,
+        "rctrl" | "rcontrol" => VK_RCONTROL,
+        "lshift" => VK_LSHIFT,
+        "rshift" => VK_RSHIFT,
+        "space" => VK_SPACE,
+        "tab" => VK_TAB,
+        "capslock" => VK_CAPITAL,
+        _ => {
+            let mut chars = lower.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() => c.to_ascii_uppercase() as i32,
+                _ => return None,
+            }
+        }
+    })
+}
+
+/// Hides or shows the cursor, tracking our own `hidden` flag so repeated
+/// calls don't drive `ShowCursor`'s internal reference count arbitrarily
+/// negative or positive: we only ever call it once per actual transition.
+unsafe fn set_cursor_hidden(hidden: bool, currently_hidden: &mut bool) {
+    if hidden && !*currently_hidden {
+        winapi::um::winuser::ShowCursor(0);
+        *currently_hidden = true;
+    } else if !hidden && *currently_hidden {
+        winapi::um::winuser::ShowCursor(1);
+        *currently_hidden = false;
+    }
 }
 
 unsafe fn get_active_process() -> Option<String> {
@@ -65,33 +150,827 @@ unsafe fn get_active_process() -> Option<String> {
     )
 }
 
+/// Restricts the cursor to `rect`, or releases any existing clip when `None`.
+///
+/// `ClipCursor` is process-global, so callers must make sure this is invoked
+/// with `None` on every exit path (button release, process switch, focus
+/// loss, shutdown) or the cursor will stay stuck in the last clipped rect.
+unsafe fn clip_cursor(rect: Option<RECT>) -> Result<()> {
+    let ptr = match &rect {
+        Some(rect) => rect as *const RECT,
+        None => std::ptr::null(),
+    };
+    if winapi::um::winuser::ClipCursor(ptr) == 0 {
+        return Err(Error::ClipCursor);
+    }
+    Ok(())
+}
+
+/// Returns the screen-space rectangle of `hwnd`'s client area, or `None` if
+/// any of the underlying Win32 calls fail (e.g. the window was closed).
+unsafe fn client_rect_on_screen(hwnd: HWND) -> Option<RECT> {
+    let mut rect: RECT = std::mem::zeroed();
+    if winapi::um::winuser::GetClientRect(hwnd, &mut rect) == 0 {
+        return None;
+    }
+    let mut top_left = winapi::shared::windef::POINT {
+        x: rect.left,
+        y: rect.top,
+    };
+    let mut bottom_right = winapi::shared::windef::POINT {
+        x: rect.right,
+        y: rect.bottom,
+    };
+    if winapi::um::winuser::ClientToScreen(hwnd, &mut top_left) == 0 {
+        return None;
+    }
+    if winapi::um::winuser::ClientToScreen(hwnd, &mut bottom_right) == 0 {
+        return None;
+    }
+    Some(RECT {
+        left: top_left.x,
+        top: top_left.y,
+        right: bottom_right.x,
+        bottom: bottom_right.y,
+    })
+}
+
+/// Returns the screen-space client rectangle of the current foreground
+/// window, used as the clip region for `Mode::Clip`.
+unsafe fn foreground_window_rect() -> Option<RECT> {
+    let hwnd = winapi::um::winuser::GetForegroundWindow();
+    if hwnd.is_null() {
+        return None;
+    }
+    client_rect_on_screen(hwnd)
+}
+
+/// Whether the clamp is allowed to engage. Toggled live from the tray menu
+/// so the loop can be suspended without killing the process.
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Set by the tray menu's "Quit" item so the poll backend's tokio loop (which
+/// doesn't otherwise see the tray window's message queue) knows to stop too.
+static SHOULD_QUIT: AtomicBool = AtomicBool::new(false);
+
+/// The currently-watched process names. Seeded from CLI args or the config
+/// file at startup; refreshed in place by `spawn_config_watcher` when the
+/// config file changes, so both backends and the tray menu see the update
+/// without a restart.
+static PROCESS_LIST: OnceLock<Mutex<Vec<OsString>>> = OnceLock::new();
+
+fn set_process_list(processes: Vec<OsString>) {
+    match PROCESS_LIST.get() {
+        Some(lock) => *lock.lock().unwrap() = processes,
+        None => {
+            PROCESS_LIST.set(Mutex::new(processes)).ok();
+        }
+    }
+}
+
+fn process_list_contains(name: &OsString) -> bool {
+    PROCESS_LIST
+        .get()
+        .map(|lock| lock.lock().unwrap().contains(name))
+        .unwrap_or(false)
+}
+
+const WM_TRAYICON: u32 = winapi::um::winuser::WM_APP + 1;
+const ID_TRAY_TOGGLE: u32 = 1;
+const ID_TRAY_QUIT: u32 = 2;
+
+/// `SetTimer` id for the hook backend's periodic re-check (see
+/// `recheck_engaged`), and how often it fires. `WM_MOUSEMOVE` alone isn't
+/// enough to notice a keyboard-only alt-tab or the tray's disable toggle
+/// while the trigger is held but the mouse sits still.
+const RECHECK_TIMER_ID: usize = 1;
+const RECHECK_INTERVAL_MS: u32 = 250;
+
+fn to_wstring(s: &str) -> Vec<u16> {
+    std::ffi::OsStr::new(s)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect()
+}
+
+unsafe fn add_tray_icon(hwnd: HWND) {
+    let mut data: NOTIFYICONDATAW = std::mem::zeroed();
+    data.cbSize = std::mem::size_of::<NOTIFYICONDATAW>() as u32;
+    data.hWnd = hwnd;
+    data.uID = 1;
+    data.uFlags = NIF_ICON | NIF_MESSAGE | NIF_TIP;
+    data.uCallbackMessage = WM_TRAYICON;
+    data.hIcon =
+        winapi::um::winuser::LoadIconW(std::ptr::null_mut(), winapi::um::winuser::IDI_APPLICATION);
+    let tip = to_wstring("cursor-clamp");
+    let len = tip.len().min(data.szTip.len());
+    data.szTip[..len].copy_from_slice(&tip[..len]);
+
+    if Shell_NotifyIconW(NIM_ADD, &mut data) == 0 {
+        log::error!("Failed to add tray icon");
+    }
+}
+
+unsafe fn remove_tray_icon(hwnd: HWND) {
+    let mut data: NOTIFYICONDATAW = std::mem::zeroed();
+    data.cbSize = std::mem::size_of::<NOTIFYICONDATAW>() as u32;
+    data.hWnd = hwnd;
+    data.uID = 1;
+    Shell_NotifyIconW(NIM_DELETE, &mut data);
+}
+
+/// Builds the right-click context menu and tracks it at the cursor: the
+/// enable/disable toggle, the watched process names (greyed out, for
+/// reference only, with the currently-active one checked), and Quit.
+unsafe fn show_tray_menu(hwnd: HWND) {
+    let menu = winapi::um::winuser::CreatePopupMenu();
+    if menu.is_null() {
+        return;
+    }
+
+    let toggle_label = if ENABLED.load(Ordering::SeqCst) {
+        "Enabled"
+    } else {
+        "Disabled"
+    };
+    winapi::um::winuser::AppendMenuW(
+        menu,
+        winapi::um::winuser::MF_STRING,
+        ID_TRAY_TOGGLE as usize,
+        to_wstring(toggle_label).as_ptr(),
+    );
+    winapi::um::winuser::AppendMenuW(menu, winapi::um::winuser::MF_SEPARATOR, 0, std::ptr::null());
+
+    let processes: Vec<String> = PROCESS_LIST
+        .get()
+        .map(|lock| {
+            lock.lock()
+                .unwrap()
+                .iter()
+                .map(|p| p.to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    if processes.is_empty() {
+        winapi::um::winuser::AppendMenuW(
+            menu,
+            winapi::um::winuser::MF_STRING | winapi::um::winuser::MF_GRAYED,
+            0,
+            to_wstring("(no processes configured)").as_ptr(),
+        );
+    } else {
+        let active_process = get_active_process();
+        for name in &processes {
+            let is_active = active_process.as_deref() == Some(name.as_str());
+            let flags = winapi::um::winuser::MF_STRING
+                | winapi::um::winuser::MF_GRAYED
+                | if is_active { winapi::um::winuser::MF_CHECKED } else { 0 };
+            // Checked to mark the process currently matching the foreground
+            // window, regardless of whether the clamp is engaged right now.
+            winapi::um::winuser::AppendMenuW(menu, flags, 0, to_wstring(name).as_ptr());
+        }
+    }
+
+    winapi::um::winuser::AppendMenuW(menu, winapi::um::winuser::MF_SEPARATOR, 0, std::ptr::null());
+    winapi::um::winuser::AppendMenuW(
+        menu,
+        winapi::um::winuser::MF_STRING,
+        ID_TRAY_QUIT as usize,
+        to_wstring("Quit").as_ptr(),
+    );
+
+    let mut point: winapi::shared::windef::POINT = std::mem::zeroed();
+    winapi::um::winuser::GetCursorPos(&mut point);
+    // TrackPopupMenu only dismisses reliably if our window is foreground.
+    winapi::um::winuser::SetForegroundWindow(hwnd);
+    winapi::um::winuser::TrackPopupMenu(
+        menu,
+        winapi::um::winuser::TPM_RIGHTBUTTON,
+        point.x,
+        point.y,
+        0,
+        hwnd,
+        std::ptr::null(),
+    );
+    winapi::um::winuser::PostMessageW(hwnd, winapi::um::winuser::WM_NULL, 0, 0);
+    winapi::um::winuser::DestroyMenu(menu);
+}
+
+unsafe extern "system" fn tray_wndproc(
+    hwnd: HWND,
+    msg: UINT,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_TRAYICON => {
+            let event = lparam as u32;
+            if event == WM_RBUTTONUP || event == WM_LBUTTONUP {
+                show_tray_menu(hwnd);
+            }
+            0
+        }
+        winapi::um::winuser::WM_COMMAND => {
+            match (wparam & 0xffff) as u32 {
+                ID_TRAY_TOGGLE => {
+                    let enabled = !ENABLED.load(Ordering::SeqCst);
+                    ENABLED.store(enabled, Ordering::SeqCst);
+                    log::info!("Clamping {}", if enabled { "enabled" } else { "disabled" });
+                }
+                ID_TRAY_QUIT => {
+                    log::info!("Quitting from tray menu");
+                    // The poll backend's loop watches `SHOULD_QUIT` itself and
+                    // releases the clamp there; the hook backend has no such
+                    // loop, so release it here before the message pump exits.
+                    if let Some(stash) = CONTEXT_STASH.get() {
+                        let mut ctx = stash.lock().unwrap();
+                        if ctx.engaged {
+                            ctx.engaged = false;
+                            ctx.held.clear();
+                            release_clamp(ctx.mode, &mut ctx.state);
+                        }
+                    }
+                    remove_tray_icon(hwnd);
+                    SHOULD_QUIT.store(true, Ordering::SeqCst);
+                    winapi::um::winuser::PostQuitMessage(0);
+                }
+                _ => {}
+            }
+            0
+        }
+        winapi::um::winuser::WM_TIMER => {
+            if wparam == RECHECK_TIMER_ID {
+                if let Some(stash) = CONTEXT_STASH.get() {
+                    let mut ctx = stash.lock().unwrap();
+                    recheck_engaged(&mut ctx);
+                }
+            }
+            0
+        }
+        winapi::um::winuser::WM_DESTROY => {
+            winapi::um::winuser::PostQuitMessage(0);
+            0
+        }
+        _ => winapi::um::winuser::DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+/// Creates the hidden message-only window that owns the tray icon and
+/// receives its `WM_APP` notifications and menu `WM_COMMAND`s.
+unsafe fn create_tray_window() -> Option<HWND> {
+    let class_name = to_wstring("CursorClampTrayWindow");
+    let hinstance = winapi::um::libloaderapi::GetModuleHandleW(std::ptr::null());
+
+    let wndclass = winapi::um::winuser::WNDCLASSW {
+        style: 0,
+        lpfnWndProc: Some(tray_wndproc),
+        cbClsExtra: 0,
+        cbWndExtra: 0,
+        hInstance: hinstance,
+        hIcon: std::ptr::null_mut(),
+        hCursor: std::ptr::null_mut(),
+        hbrBackground: std::ptr::null_mut(),
+        lpszMenuName: std::ptr::null(),
+        lpszClassName: class_name.as_ptr(),
+    };
+    if winapi::um::winuser::RegisterClassW(&wndclass) == 0 {
+        log::error!("Failed to register tray window class");
+        return None;
+    }
+
+    let window_name = to_wstring("cursor-clamp");
+    let hwnd = winapi::um::winuser::CreateWindowExW(
+        0,
+        class_name.as_ptr(),
+        window_name.as_ptr(),
+        0,
+        0,
+        0,
+        0,
+        0,
+        winapi::um::winuser::HWND_MESSAGE,
+        std::ptr::null_mut(),
+        hinstance,
+        std::ptr::null_mut(),
+    );
+    if hwnd.is_null() {
+        log::error!("Failed to create tray window");
+        return None;
+    }
+
+    add_tray_icon(hwnd);
+    Some(hwnd)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Backend {
+    /// Busy-poll `GetAsyncKeyState` every `interval` milliseconds.
+    Poll,
+    /// Install a `WH_MOUSE_LL` hook and react to button messages as they
+    /// arrive, with no polling interval and no missed button-down instants.
+    Hook,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    /// Remember the cursor position on button-down and teleport it back on
+    /// button-up, as before.
+    Restore,
+    /// Confine the cursor to the foreground window's rectangle with
+    /// `ClipCursor` for as long as the button is held.
+    Clip,
+}
+
+/// On-disk mirror of `Opts`, loaded from `--config` (or its default path)
+/// and merged underneath the CLI args: a field set on the command line
+/// always wins, falling back to this file, then to the built-in default.
+///
+/// `processes` is the one field that stays live after startup — see
+/// `spawn_config_watcher`.
+#[derive(Default, Deserialize)]
+struct ConfigFile {
+    processes: Option<Vec<String>>,
+    triggers: Option<Vec<String>>,
+    interval: Option<u64>,
+    mode: Option<Mode>,
+    backend: Option<Backend>,
+    hide_cursor: Option<bool>,
+}
+
+/// `%APPDATA%\cursor-clamp\config.toml`, or `cursor-clamp.toml` in the
+/// current directory if `%APPDATA%` isn't set.
+fn default_config_path() -> PathBuf {
+    match std::env::var_os("APPDATA") {
+        Some(appdata) => Path::new(&appdata).join("cursor-clamp").join("config.toml"),
+        None => PathBuf::from("cursor-clamp.toml"),
+    }
+}
+
+/// Reads and parses `path` as a `ConfigFile`. Returns `None` (silently) if
+/// the file doesn't exist, or (with a logged error) if it exists but can't
+/// be read or parsed.
+fn load_config_file(path: &Path) -> Option<ConfigFile> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(err) => {
+            log::error!("Failed to read config file {}: {}", path.display(), err);
+            return None;
+        }
+    };
+    match toml::from_str(&text) {
+        Ok(config) => Some(config),
+        Err(err) => {
+            log::error!("Failed to parse config file {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+/// Polls `path`'s mtime every couple of seconds and, whenever it changes,
+/// refreshes `PROCESS_LIST` from the file's `processes` entry. Only spawned
+/// when no processes were given on the CLI, so a config-driven process
+/// list can pick up a newly added game executable without a restart, while
+/// an explicit CLI list stays authoritative for the life of the process.
+fn spawn_config_watcher(path: PathBuf) {
+    std::thread::spawn(move || {
+        let mut last_modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        loop {
+            std::thread::sleep(Duration::from_secs(2));
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+            if let Some(config) = load_config_file(&path) {
+                if let Some(processes) = config.processes {
+                    log::info!(
+                        "Reloaded {} watched process(es) from {}",
+                        processes.len(),
+                        path.display()
+                    );
+                    set_process_list(processes.into_iter().map(OsString::from).collect());
+                }
+            }
+        }
+    });
+}
+
 #[derive(Parser)]
 struct Opts {
     /// Process names to enable locking the mouse for. Can specify multiple processes.
     ///
+    /// Falls back to the `processes` list in the config file (see
+    /// `--config`) if none are given here; that file is then hot-reloaded,
+    /// so an explicit CLI list is the only way to opt out of reloading.
+    ///
     /// Example:
     /// $ cursor-clamp "Wow.exe" "Gw2-64.exe"
     processes: Vec<OsString>,
 
-    /// The button to lock the mouse with.
+    /// A button or key that engages the clamp while held. Can be given
+    /// multiple times; the clamp engages if *any* of them is held.
     ///
-    /// 1: Left mouse button
+    /// Accepts a numeric virtual-key code, or a symbolic name: mouse1/mouse2
+    /// (default)/mouse3/mouse4/mouse5 for the left/right/middle/X1/X2 mouse
+    /// buttons, lalt/ralt/lctrl/rctrl/lshift/rshift/space/tab/capslock for
+    /// common keyboard keys, or a single letter. A bare digit is read as a
+    /// numeric virtual-key code, not as the digit key.
     ///
-    /// 2: Right mouse button (default)
+    /// Migrating from the old `--button` flag: `1`/`2`/`3` are kept as the
+    /// left/right/middle mouse enum they used to be, not raw VK codes (VK_MBUTTON
+    /// is actually 4) — use a numeric VK code above 3 or a symbolic name for
+    /// anything else.
     ///
-    /// 3: Middle mouse button
+    /// Falls back to the config file's `triggers`, then to `mouse2`.
     ///
     /// Example:
-    /// $ cursor-clamp "Wow.exe" --button 1
-    #[clap(short, long, default_value = "2")]
-    button: i32,
+    /// $ cursor-clamp "Wow.exe" --trigger mouse2 --trigger lalt
+    #[clap(short, long = "trigger")]
+    triggers: Vec<String>,
 
     /// The interval in milliseconds to check the mouse button state.
     ///
+    /// Falls back to the config file's `interval`, then to `1`.
+    ///
     /// Example:
     /// $ cursor-clamp "Wow.exe" --interval 100
-    #[clap(short, long, default_value = "1")]
-    interval: u64,
+    #[clap(short, long)]
+    interval: Option<u64>,
+
+    /// How the cursor is held in place while the button is down.
+    ///
+    /// restore: teleport the cursor back to where it was on button-up (default)
+    ///
+    /// clip: confine the cursor to the foreground window with `ClipCursor` for
+    /// as long as the button is held
+    ///
+    /// Falls back to the config file's `mode`, then to `restore`.
+    ///
+    /// Example:
+    /// $ cursor-clamp "Wow.exe" --mode clip
+    #[clap(long, value_enum)]
+    mode: Option<Mode>,
+
+    /// Which mechanism is used to detect the button press.
+    ///
+    /// poll: check `GetAsyncKeyState` every `interval` milliseconds
+    ///
+    /// hook: install a low-level mouse hook and react to button events as
+    /// they happen, with no polling interval (default)
+    ///
+    /// Falls back to the config file's `backend`, then to `hook`.
+    ///
+    /// Example:
+    /// $ cursor-clamp "Wow.exe" --backend poll
+    #[clap(long, value_enum)]
+    backend: Option<Backend>,
+
+    /// Hide the OS cursor for as long as the button is held, in addition to
+    /// whatever `--mode` is doing with its position.
+    ///
+    /// Also set if the config file's `hide_cursor` is true.
+    ///
+    /// Example:
+    /// $ cursor-clamp "Wow.exe" --hide-cursor
+    #[clap(long)]
+    hide_cursor: bool,
+
+    /// Path to the TOML config file to load. Holds the same settings as
+    /// these CLI flags; anything passed here on the command line overrides
+    /// the corresponding file value.
+    ///
+    /// Defaults to `%APPDATA%\cursor-clamp\config.toml`.
+    ///
+    /// Example:
+    /// $ cursor-clamp --config C:\Users\me\cursor-clamp.toml
+    #[clap(long)]
+    config: Option<PathBuf>,
+}
+
+/// Cursor state that's carried across an engage/release pair: the position
+/// to restore for `Mode::Restore`, and whether we're the one who hid the
+/// cursor (so we know whether to show it back on release).
+#[derive(Default)]
+struct ClampState {
+    mouse_position: (i32, i32),
+    cursor_hidden: bool,
+}
+
+/// Engages the clamp for `mode` on button-down, updating `state` in place.
+unsafe fn engage_clamp(mode: Mode, hide_cursor: bool, state: &mut ClampState) {
+    match mode {
+        Mode::Restore => {
+            if let Ok(new_mouse_position) = get_mouse_position() {
+                state.mouse_position = new_mouse_position;
+            }
+            log::info!("Remembering mouse position: {:?}", state.mouse_position);
+        }
+        Mode::Clip => {
+            if let Some(rect) = foreground_window_rect() {
+                if let Err(err) = clip_cursor(Some(rect)) {
+                    log::error!("Failed to clip cursor: {}", err);
+                } else {
+                    log::info!("Clipping cursor to {:?}", rect);
+                }
+            }
+        }
+    }
+    if hide_cursor {
+        set_cursor_hidden(true, &mut state.cursor_hidden);
+    }
+}
+
+/// Releases whatever clamp is currently engaged for `mode`, and shows the
+/// cursor back if we're the one who hid it. Called on every path out of the
+/// held state: button-up, process switch, focus loss, and shutdown.
+unsafe fn release_clamp(mode: Mode, state: &mut ClampState) {
+    match mode {
+        Mode::Restore => {
+            if let Err(err) = set_mouse_position(state.mouse_position.0, state.mouse_position.1) {
+                log::error!("Failed to set mouse position: {}", err);
+            } else {
+                log::info!("Set mouse position: {:?}", state.mouse_position);
+            }
+        }
+        Mode::Clip => {
+            if let Err(err) = clip_cursor(None) {
+                log::error!("Failed to release cursor clip: {}", err);
+            } else {
+                log::info!("Released cursor clip");
+            }
+        }
+    }
+    set_cursor_hidden(false, &mut state.cursor_hidden);
+}
+
+/// A trigger VK going down or up, or an incidental mouse move — independent
+/// of whether a `WH_MOUSE_LL` or `WH_KEYBOARD_LL` hook produced it.
+enum TriggerEvent {
+    Down(i32),
+    Up(i32),
+    MouseMove,
+}
+
+/// Which VK an X1/X2 mouse button message refers to: low-level mouse hooks
+/// encode that in the high word of `mouseData`, the same convention
+/// `GET_XBUTTON_WPARAM` uses for ordinary `WM_XBUTTONDOWN` messages.
+fn vk_for_xbutton(mouse_data: u32) -> i32 {
+    if (mouse_data >> 16) & 0xffff == 2 {
+        winapi::um::winuser::VK_XBUTTON2
+    } else {
+        winapi::um::winuser::VK_XBUTTON1
+    }
+}
+
+fn mouse_trigger_event(msg: u32, mouse_data: u32) -> Option<TriggerEvent> {
+    Some(match msg {
+        WM_LBUTTONDOWN => TriggerEvent::Down(LEFT_MOUSE_BUTTON),
+        WM_LBUTTONUP => TriggerEvent::Up(LEFT_MOUSE_BUTTON),
+        WM_RBUTTONDOWN => TriggerEvent::Down(RIGHT_MOUSE_BUTTON),
+        WM_RBUTTONUP => TriggerEvent::Up(RIGHT_MOUSE_BUTTON),
+        WM_MBUTTONDOWN => TriggerEvent::Down(MIDDLE_MOUSE_BUTTON),
+        WM_MBUTTONUP => TriggerEvent::Up(MIDDLE_MOUSE_BUTTON),
+        WM_XBUTTONDOWN => TriggerEvent::Down(vk_for_xbutton(mouse_data)),
+        WM_XBUTTONUP => TriggerEvent::Up(vk_for_xbutton(mouse_data)),
+        WM_MOUSEMOVE => TriggerEvent::MouseMove,
+        _ => return None,
+    })
+}
+
+/// Resolves a keyboard hook's `vkCode` to a left/right-specific VK for Ctrl
+/// and Alt, using the `LLKHF_EXTENDED` flag the way Windows documents (the
+/// right-hand key of each pair is "extended"). Windows doesn't reliably
+/// expose the same distinction for Shift at this layer, so `lshift`/`rshift`
+/// are only precise with `--backend poll`, which reads them directly via
+/// `GetAsyncKeyState`.
+fn normalize_keyboard_vk(vk_code: i32, flags: u32) -> i32 {
+    use winapi::um::winuser::{LLKHF_EXTENDED, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_MENU, VK_RCONTROL, VK_RMENU};
+    let extended = flags & LLKHF_EXTENDED != 0;
+    match vk_code {
+        vk if vk == VK_MENU => {
+            if extended {
+                VK_RMENU
+            } else {
+                VK_LMENU
+            }
+        }
+        vk if vk == VK_CONTROL => {
+            if extended {
+                VK_RCONTROL
+            } else {
+                VK_LCONTROL
+            }
+        }
+        other => other,
+    }
+}
+
+fn keyboard_trigger_event(msg: u32, vk_code: i32, flags: u32) -> Option<TriggerEvent> {
+    let vk = normalize_keyboard_vk(vk_code, flags);
+    match msg {
+        WM_KEYDOWN | WM_SYSKEYDOWN => Some(TriggerEvent::Down(vk)),
+        WM_KEYUP | WM_SYSKEYUP => Some(TriggerEvent::Up(vk)),
+        _ => None,
+    }
+}
+
+/// State reachable from the hook callbacks, which run on the thread that
+/// installed the hooks and can't be handed a closure's captures directly.
+/// Mirrors glutin's `CONTEXT_STASH` pattern for its raw-input window proc.
+struct HookContext {
+    triggers: Vec<i32>,
+    /// Configured triggers currently observed held down. The clamp engages
+    /// when this goes from empty to non-empty, and releases when it goes
+    /// back to empty, so chording two triggers doesn't release on the first
+    /// one let go.
+    held: std::collections::HashSet<i32>,
+    mode: Mode,
+    hide_cursor: bool,
+    state: ClampState,
+    engaged: bool,
+}
+
+static CONTEXT_STASH: OnceLock<Mutex<HookContext>> = OnceLock::new();
+
+unsafe extern "system" fn low_level_mouse_proc(
+    code: i32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if code >= 0 {
+        let hook_struct = &*(lparam as *const MSLLHOOKSTRUCT);
+        if let Some(event) = mouse_trigger_event(wparam as u32, hook_struct.mouseData) {
+            if let Some(stash) = CONTEXT_STASH.get() {
+                let mut ctx = stash.lock().unwrap();
+                handle_trigger_event(&mut ctx, event);
+            }
+        }
+    }
+    winapi::um::winuser::CallNextHookEx(std::ptr::null_mut(), code, wparam, lparam)
+}
+
+unsafe extern "system" fn low_level_keyboard_proc(
+    code: i32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if code >= 0 {
+        let hook_struct = &*(lparam as *const KBDLLHOOKSTRUCT);
+        if let Some(event) =
+            keyboard_trigger_event(wparam as u32, hook_struct.vkCode as i32, hook_struct.flags)
+        {
+            if let Some(stash) = CONTEXT_STASH.get() {
+                let mut ctx = stash.lock().unwrap();
+                handle_trigger_event(&mut ctx, event);
+            }
+        }
+    }
+    winapi::um::winuser::CallNextHookEx(std::ptr::null_mut(), code, wparam, lparam)
+}
+
+unsafe fn handle_trigger_event(ctx: &mut HookContext, event: TriggerEvent) {
+    match event {
+        TriggerEvent::Down(vk) => {
+            if !ctx.triggers.contains(&vk) {
+                return;
+            }
+            let first_press = ctx.held.is_empty();
+            ctx.held.insert(vk);
+            if first_press && !ctx.engaged {
+                let matches = ENABLED.load(Ordering::Relaxed)
+                    && get_active_process()
+                        .map(|p| process_list_contains(&OsString::from(p)))
+                        .unwrap_or(false);
+                if matches {
+                    ctx.engaged = true;
+                    engage_clamp(ctx.mode, ctx.hide_cursor, &mut ctx.state);
+                }
+            }
+        }
+        TriggerEvent::Up(vk) => {
+            if !ctx.triggers.contains(&vk) {
+                return;
+            }
+            ctx.held.remove(&vk);
+            if ctx.held.is_empty() && ctx.engaged {
+                ctx.engaged = false;
+                release_clamp(ctx.mode, &mut ctx.state);
+            }
+        }
+        TriggerEvent::MouseMove => {
+            // Also re-check on incidental mouse movement so a moved/resized
+            // foreground window gets re-clipped promptly; `RECHECK_TIMER_ID`
+            // covers the case where the mouse doesn't move at all.
+            recheck_engaged(ctx);
+        }
+    }
+}
+
+/// Re-validates an already-engaged clamp: releases it if the active process
+/// no longer matches or clamping was disabled from the tray, otherwise
+/// re-applies `Mode::Clip`'s rect in case the foreground window moved or
+/// resized. Driven by both incidental `WM_MOUSEMOVE` events and
+/// `RECHECK_TIMER_ID`'s timer, since the hook backend otherwise has no tick
+/// of its own and a keyboard-only focus change with the mouse held still
+/// would leave the clamp stuck until the next mouse movement.
+unsafe fn recheck_engaged(ctx: &mut HookContext) {
+    if !ctx.engaged {
+        return;
+    }
+    let matches = ENABLED.load(Ordering::Relaxed)
+        && get_active_process()
+            .map(|p| process_list_contains(&OsString::from(p)))
+            .unwrap_or(false);
+    if !matches {
+        ctx.engaged = false;
+        ctx.held.clear();
+        release_clamp(ctx.mode, &mut ctx.state);
+    } else if ctx.mode == Mode::Clip {
+        if let Some(rect) = foreground_window_rect() {
+            let _ = clip_cursor(Some(rect));
+        }
+    }
+}
+
+/// Runs the event-driven backend: installs `WH_MOUSE_LL`/`WH_KEYBOARD_LL`
+/// hooks and the tray window on this thread, then pumps its message queue,
+/// which Windows requires for the hooks to be called in a timely fashion and
+/// which also delivers the tray icon's `WM_APP` notifications and menu
+/// commands.
+unsafe fn run_hook_backend(triggers: Vec<i32>, mode: Mode, hide_cursor: bool) {
+    CONTEXT_STASH
+        .set(Mutex::new(HookContext {
+            triggers,
+            held: std::collections::HashSet::new(),
+            mode,
+            hide_cursor,
+            state: ClampState::default(),
+            engaged: false,
+        }))
+        .ok();
+
+    let mouse_hook: HHOOK = winapi::um::winuser::SetWindowsHookExW(
+        winapi::um::winuser::WH_MOUSE_LL,
+        Some(low_level_mouse_proc),
+        std::ptr::null_mut(),
+        0,
+    );
+    if mouse_hook.is_null() {
+        log::error!("Failed to install mouse hook");
+        return;
+    }
+
+    // A keyboard hook too, so symbolic keyboard triggers like `lalt` work.
+    let keyboard_hook: HHOOK = winapi::um::winuser::SetWindowsHookExW(
+        winapi::um::winuser::WH_KEYBOARD_LL,
+        Some(low_level_keyboard_proc),
+        std::ptr::null_mut(),
+        0,
+    );
+    if keyboard_hook.is_null() {
+        log::error!("Failed to install keyboard hook");
+    }
+
+    let tray_hwnd = create_tray_window();
+    if let Some(hwnd) = tray_hwnd {
+        // Without this, the only re-check of "is the clamp still valid" is
+        // piggy-backed on incidental WM_MOUSEMOVE events (see
+        // `recheck_engaged`), so a keyboard-only alt-tab or the tray's
+        // disable toggle wouldn't release the clamp until the mouse moved.
+        winapi::um::winuser::SetTimer(hwnd, RECHECK_TIMER_ID, RECHECK_INTERVAL_MS, None);
+    }
+
+    let mut msg: winapi::um::winuser::MSG = std::mem::zeroed();
+    while winapi::um::winuser::GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+        winapi::um::winuser::TranslateMessage(&msg);
+        winapi::um::winuser::DispatchMessageW(&msg);
+    }
+
+    if let Some(hwnd) = tray_hwnd {
+        winapi::um::winuser::KillTimer(hwnd, RECHECK_TIMER_ID);
+    }
+    winapi::um::winuser::UnhookWindowsHookEx(mouse_hook);
+    if !keyboard_hook.is_null() {
+        winapi::um::winuser::UnhookWindowsHookEx(keyboard_hook);
+    }
+}
+
+/// Runs the tray icon's own message pump on a dedicated thread, for the poll
+/// backend where the main loop is an async tokio task with no Win32 message
+/// queue of its own.
+unsafe fn run_tray_message_loop() {
+    if create_tray_window().is_none() {
+        return;
+    }
+
+    let mut msg: winapi::um::winuser::MSG = std::mem::zeroed();
+    while winapi::um::winuser::GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+        winapi::um::winuser::TranslateMessage(&msg);
+        winapi::um::winuser::DispatchMessageW(&msg);
+    }
 }
 
 #[tokio::main]
@@ -100,61 +979,115 @@ async fn main() {
         .parse_env(env_logger::Env::new().default_filter_or("info"))
         .init();
 
-    let mut mouse_position = (0, 0);
+    let mut state = ClampState::default();
     let mut mouse_pressed = false;
     let mut last_mouse_pressed = false;
 
     let opts: Opts = Opts::parse();
 
-    let button = match opts.button {
-        1 => LEFT_MOUSE_BUTTON,
-        2 => RIGHT_MOUSE_BUTTON,
-        3 => MIDDLE_MOUSE_BUTTON,
-        _ => {
-            log::error!("Invalid button value");
-            return;
-        }
+    let config_path = opts.config.clone().unwrap_or_else(default_config_path);
+    let config = load_config_file(&config_path).unwrap_or_default();
+
+    let explicit_processes = !opts.processes.is_empty();
+    let processes: Vec<OsString> = if explicit_processes {
+        opts.processes
+    } else {
+        config
+            .processes
+            .unwrap_or_default()
+            .into_iter()
+            .map(OsString::from)
+            .collect()
     };
+    if processes.is_empty() {
+        log::error!(
+            "No processes configured; pass at least one on the command line or list them under \
+             `processes` in {}",
+            config_path.display()
+        );
+        return;
+    }
+    set_process_list(processes);
+
+    let raw_triggers = if !opts.triggers.is_empty() {
+        opts.triggers
+    } else {
+        config.triggers.unwrap_or_else(|| vec!["mouse2".to_string()])
+    };
+    let mut triggers = Vec::with_capacity(raw_triggers.len());
+    for raw in &raw_triggers {
+        match parse_trigger(raw) {
+            Some(vk) => triggers.push(vk),
+            None => {
+                log::error!("Unrecognized trigger: {}", raw);
+                return;
+            }
+        }
+    }
 
-    let processes = opts.processes;
+    let mode = opts.mode.or(config.mode).unwrap_or(Mode::Restore);
+    let backend = opts.backend.or(config.backend).unwrap_or(Backend::Hook);
+    let hide_cursor = opts.hide_cursor || config.hide_cursor.unwrap_or(false);
+    let interval = opts.interval.or(config.interval).unwrap_or(1);
 
-    let interval = Duration::from_millis(opts.interval);
+    // Only reload the process list live if it wasn't pinned on the command
+    // line; an explicit positional process list is authoritative for
+    // the life of the process, same as every other CLI-vs-file field.
+    if !explicit_processes {
+        spawn_config_watcher(config_path);
+    }
+
+    if backend == Backend::Hook {
+        unsafe { run_hook_backend(triggers, mode, hide_cursor) };
+        return;
+    }
+
+    // The tray icon needs its own Win32 message pump; run it on a dedicated
+    // thread since this backend's main loop is an async tokio task.
+    std::thread::spawn(move || unsafe { run_tray_message_loop() });
+
+    let interval = Duration::from_millis(interval);
 
     loop {
+        if SHOULD_QUIT.load(Ordering::SeqCst) {
+            if mouse_pressed {
+                unsafe { release_clamp(mode, &mut state) };
+            }
+            log::info!("Quitting");
+            return;
+        }
+
         let active_process = unsafe { get_active_process() };
         if let Some(active_process) = active_process {
-            if processes.contains(&OsString::from(active_process)) {
-                if unsafe { get_mouse_button_pressed(button) } {
+            if process_list_contains(&OsString::from(active_process)) && ENABLED.load(Ordering::SeqCst)
+            {
+                if triggers.iter().any(|&vk| unsafe { is_trigger_held(vk) }) {
                     if !mouse_pressed {
                         mouse_pressed = true;
-
-                        if let Ok(new_mouse_position) = unsafe { get_mouse_position() } {
-                            mouse_position = new_mouse_position;
+                        unsafe { engage_clamp(mode, hide_cursor, &mut state) };
+                    } else if mode == Mode::Clip {
+                        // The foreground window may have moved or resized since the
+                        // last clip, so re-derive and re-apply the rect every tick.
+                        if let Some(rect) = unsafe { foreground_window_rect() } {
+                            if let Err(err) = unsafe { clip_cursor(Some(rect)) } {
+                                log::error!("Failed to clip cursor: {}", err);
+                            }
                         }
-
-                        log::info!("Remembering mouse position: {:?}", mouse_position);
                     }
                 } else if mouse_pressed {
                     mouse_pressed = false;
-
-                    if let Err(err) =
-                        unsafe { set_mouse_position(mouse_position.0, mouse_position.1) }
-                    {
-                        log::error!("Failed to set mouse position: {}", err);
-                    }
-                    log::info!("Set mouse position: {:?}", mouse_position);
+                    unsafe { release_clamp(mode, &mut state) };
                 }
             } else if mouse_pressed {
                 mouse_pressed = false;
-
-                if let Err(err) = unsafe { set_mouse_position(mouse_position.0, mouse_position.1) }
-                {
-                    log::error!("Failed to set mouse position: {}", err);
-                }
-                log::info!("Set mouse position: {:?}", mouse_position);
+                unsafe { release_clamp(mode, &mut state) };
             }
         } else {
             log::error!("Failed to get active process");
+            if mouse_pressed {
+                mouse_pressed = false;
+                unsafe { release_clamp(mode, &mut state) };
+            }
         }
 
         if mouse_pressed != last_mouse_pressed {
@@ -164,3 +1097,68 @@ async fn main() {
         tokio::time::sleep(interval).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_trigger_keeps_legacy_button_enum_for_1_2_3() {
+        assert_eq!(parse_trigger("1"), Some(LEFT_MOUSE_BUTTON));
+        assert_eq!(parse_trigger("2"), Some(RIGHT_MOUSE_BUTTON));
+        assert_eq!(parse_trigger("3"), Some(MIDDLE_MOUSE_BUTTON));
+    }
+
+    #[test]
+    fn parse_trigger_accepts_raw_vk_codes_above_3() {
+        assert_eq!(parse_trigger("13"), Some(13));
+        assert_eq!(parse_trigger("112"), Some(112));
+    }
+
+    #[test]
+    fn parse_trigger_accepts_symbolic_mouse_and_keyboard_names() {
+        assert_eq!(parse_trigger("mouse2"), Some(RIGHT_MOUSE_BUTTON));
+        assert_eq!(parse_trigger("rmb"), Some(RIGHT_MOUSE_BUTTON));
+        assert_eq!(parse_trigger("mouse4"), Some(winapi::um::winuser::VK_XBUTTON1));
+        assert_eq!(parse_trigger("mouse5"), Some(winapi::um::winuser::VK_XBUTTON2));
+        assert_eq!(parse_trigger("LAlt"), Some(winapi::um::winuser::VK_LMENU));
+        assert_eq!(parse_trigger("capslock"), Some(winapi::um::winuser::VK_CAPITAL));
+    }
+
+    #[test]
+    fn parse_trigger_accepts_a_single_letter() {
+        assert_eq!(parse_trigger("q"), Some('Q' as i32));
+        assert_eq!(parse_trigger("Q"), Some('Q' as i32));
+    }
+
+    #[test]
+    fn parse_trigger_rejects_unrecognized_input() {
+        assert_eq!(parse_trigger("not-a-trigger"), None);
+        assert_eq!(parse_trigger(""), None);
+    }
+
+    #[test]
+    fn vk_for_xbutton_decodes_the_high_word_of_mouse_data() {
+        // GET_XBUTTON_WPARAM-style encoding: XBUTTON1/XBUTTON2 in the high word.
+        assert_eq!(vk_for_xbutton(1 << 16), winapi::um::winuser::VK_XBUTTON1);
+        assert_eq!(vk_for_xbutton(2 << 16), winapi::um::winuser::VK_XBUTTON2);
+        // Anything other than exactly XBUTTON2 (2) falls back to XBUTTON1.
+        assert_eq!(vk_for_xbutton(0), winapi::um::winuser::VK_XBUTTON1);
+    }
+
+    #[test]
+    fn normalize_keyboard_vk_splits_ctrl_and_alt_by_extended_flag() {
+        use winapi::um::winuser::{LLKHF_EXTENDED, VK_CONTROL, VK_LCONTROL, VK_LMENU, VK_MENU, VK_RCONTROL, VK_RMENU};
+        assert_eq!(normalize_keyboard_vk(VK_MENU, 0), VK_LMENU);
+        assert_eq!(normalize_keyboard_vk(VK_MENU, LLKHF_EXTENDED), VK_RMENU);
+        assert_eq!(normalize_keyboard_vk(VK_CONTROL, 0), VK_LCONTROL);
+        assert_eq!(normalize_keyboard_vk(VK_CONTROL, LLKHF_EXTENDED), VK_RCONTROL);
+    }
+
+    #[test]
+    fn normalize_keyboard_vk_passes_through_other_keys_unchanged() {
+        let vk_space = winapi::um::winuser::VK_SPACE;
+        assert_eq!(normalize_keyboard_vk(vk_space, 0), vk_space);
+        assert_eq!(normalize_keyboard_vk(vk_space, winapi::um::winuser::LLKHF_EXTENDED), vk_space);
+    }
+}